@@ -0,0 +1,130 @@
+//! Checks locked dependencies against the RustSec advisory database for
+//! `--audit`.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use tempdir::TempDir;
+use toml;
+
+use deps::Advisory;
+use error::{CliError, CliResult};
+use util;
+
+/// The RustSec advisory database, cloned locally so every package's
+/// advisories can be scanned without a per-package network round-trip.
+const ADVISORY_DB_URL: &str = "https://github.com/RustSec/advisory-db";
+
+/// Clones the advisory database and returns, for every package in `locked`
+/// that has one, the advisory matching its currently-locked version.
+pub fn check(locked: &BTreeMap<String, String>) -> CliResult<BTreeMap<String, Advisory>> {
+    let dir = clone_db()?;
+
+    let mut vulnerable = BTreeMap::new();
+    for (name, version) in locked {
+        let dir_path = dir.path().join("crates").join(name);
+        let entries = match dir_path.read_dir() {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            if let Some(advisory) = parse_advisory(&entry.path(), version) {
+                vulnerable.insert(name.clone(), advisory);
+                break;
+            }
+        }
+    }
+
+    Ok(vulnerable)
+}
+
+/// Clones the advisory database into a scratch directory.
+fn clone_db() -> CliResult<TempDir> {
+    let dir = TempDir::new("cargo-outdated-advisory-db")?;
+    let status = ::std::process::Command::new("git")
+        .args(["clone", "--depth", "1", ADVISORY_DB_URL])
+        .arg(dir.path())
+        .status();
+    match status {
+        Ok(ref s) if s.success() => Ok(dir),
+        _ => Err(CliError::Generic("failed to clone the RustSec advisory database".to_owned())),
+    }
+}
+
+/// Parses a single `RUSTSEC-*.toml` advisory file and returns it if `version`
+/// falls within its vulnerable range.
+fn parse_advisory(path: &Path, version: &str) -> Option<Advisory> {
+    let mut s = String::new();
+    File::open(path).ok()?.read_to_string(&mut s).ok()?;
+    let doc: toml::Value = s.parse().ok()?;
+
+    let id = doc.get("advisory")?.get("id")?.as_str()?.to_owned();
+
+    let patched: Vec<String> = doc.get("versions")
+        .and_then(|v| v.get("patched"))
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let unaffected: Vec<String> = doc.get("versions")
+        .and_then(|v| v.get("unaffected"))
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+
+    let is_safe = patched.iter().chain(unaffected.iter())
+        .any(|req| util::satisfies(version, req));
+
+    if is_safe {
+        None
+    } else {
+        Some(Advisory { id, patched })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn write_advisory(dir: &Path, toml: &str) -> ::std::path::PathBuf {
+        let path = dir.join("RUSTSEC-0000-0000.toml");
+        File::create(&path).unwrap().write_all(toml.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn parse_advisory_flags_a_vulnerable_version() {
+        let dir = TempDir::new("cargo-outdated-test").unwrap();
+        let path = write_advisory(dir.path(),
+                                   "[advisory]\nid = \"RUSTSEC-2020-0001\"\n\n[versions]\n\
+                                    patched = [\">= 1.2.3\"]\n");
+
+        let advisory = parse_advisory(&path, "1.0.0").unwrap();
+        assert_eq!(advisory.id, "RUSTSEC-2020-0001");
+        assert_eq!(advisory.patched, vec![">= 1.2.3".to_owned()]);
+    }
+
+    #[test]
+    fn parse_advisory_ignores_a_patched_version() {
+        let dir = TempDir::new("cargo-outdated-test").unwrap();
+        let path = write_advisory(dir.path(),
+                                   "[advisory]\nid = \"RUSTSEC-2020-0001\"\n\n[versions]\n\
+                                    patched = [\">= 1.2.3\"]\n");
+
+        assert!(parse_advisory(&path, "1.2.3").is_none());
+    }
+
+    #[test]
+    fn parse_advisory_ignores_an_unaffected_version() {
+        let dir = TempDir::new("cargo-outdated-test").unwrap();
+        let path = write_advisory(dir.path(),
+                                   "[advisory]\nid = \"RUSTSEC-2020-0001\"\n\n[versions]\n\
+                                    patched = [\">= 1.2.3\"]\nunaffected = [\"< 0.5.0\"]\n");
+
+        assert!(parse_advisory(&path, "0.1.0").is_none());
+    }
+}
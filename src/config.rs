@@ -0,0 +1,117 @@
+//! Parses `clap` matches into a typed configuration for the rest of the tool.
+
+use std::env;
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+
+use error::CliResult;
+
+/// How `execute` should render the outdated-dependency report.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The default aligned, human-readable table.
+    Human,
+    /// A JSON array, one object per outdated dependency, for scripts/CI to consume.
+    Json,
+}
+
+/// Resolved configuration for a single `cargo outdated` run.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Absolute path to the `Cargo.toml` being inspected.
+    pub manifest_path: PathBuf,
+    /// Absolute path to the `Cargo.lock` being inspected.
+    pub lockfile: PathBuf,
+    /// Only packages in this list are reported, when non-empty.
+    pub packages: Vec<String>,
+    /// Package to treat as the dependency graph's root, if overridden.
+    pub root: Option<String>,
+    /// How deep into the dependency graph to search.
+    pub depth: Option<usize>,
+    /// Print extra progress information while running.
+    pub verbose: bool,
+    /// Exit code to return when outdated dependencies are found.
+    pub exit_code: i32,
+    /// Output format for the final report.
+    pub format: OutputFormat,
+    /// Rewrite `Cargo.toml` requirements for outdated dependencies in place.
+    pub upgrade: bool,
+    /// With `upgrade`, report what would change without touching the manifest.
+    pub dry_run: bool,
+    /// With `upgrade`, also rewrite requirements that are already semver-compatible
+    /// with the latest version (not just breaking ones).
+    pub upgrade_compatible: bool,
+    /// With `upgrade`, rewrite pinned (`=`) requirements too.
+    pub force: bool,
+    /// Check locked dependencies against the RustSec advisory database.
+    pub audit: bool,
+    /// Exit code to return when `--audit` finds a vulnerable dependency.
+    pub audit_exit_code: i32,
+    /// Only report/print semver-compatible updates.
+    pub compatible_only: bool,
+    /// Only report/print semver-incompatible (breaking) updates.
+    pub incompatible_only: bool,
+    /// Exit code to return when only compatible updates are present.
+    pub compatible_exit_code: i32,
+    /// Exit code to return when any incompatible (breaking) update is present.
+    pub incompatible_exit_code: i32,
+    /// Report every member of the workspace, not just the root package.
+    pub workspace: bool,
+    /// Workspace member names to skip when reporting.
+    pub exclude: Vec<String>,
+}
+
+impl Config {
+    /// Builds a `Config` from the parsed `outdated` subcommand matches.
+    pub fn from_matches(m: &ArgMatches) -> CliResult<Config> {
+        let cwd = env::current_dir()?;
+
+        let manifest_path = m.value_of("manifest-path")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| cwd.join("Cargo.toml"));
+        let lockfile = m.value_of("lockfile-path")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| cwd.join("Cargo.lock"));
+
+        let depth = if m.is_present("root-deps-only") {
+            Some(1)
+        } else {
+            m.value_of("depth").and_then(|d| d.parse().ok())
+        };
+
+        let format = match m.value_of("format") {
+            Some("json") => OutputFormat::Json,
+            _ => OutputFormat::Human,
+        };
+
+        let exit_code = value_t!(m, "exit-code", i32).unwrap_or(0);
+
+        Ok(Config {
+            manifest_path,
+            lockfile,
+            packages: m.values_of("package")
+                .map(|v| v.map(String::from).collect())
+                .unwrap_or_default(),
+            root: m.value_of("root").map(String::from),
+            depth,
+            verbose: m.is_present("verbose"),
+            exit_code,
+            format,
+            upgrade: m.is_present("upgrade"),
+            dry_run: m.is_present("dry-run"),
+            upgrade_compatible: m.is_present("compatible"),
+            force: m.is_present("force"),
+            audit: m.is_present("audit"),
+            audit_exit_code: value_t!(m, "audit-exit-code", i32).unwrap_or(1),
+            compatible_only: m.is_present("compatible-only"),
+            incompatible_only: m.is_present("incompatible-only"),
+            compatible_exit_code: value_t!(m, "compatible-exit-code", i32).unwrap_or(exit_code),
+            incompatible_exit_code: value_t!(m, "incompatible-exit-code", i32).unwrap_or(exit_code),
+            workspace: m.is_present("workspace") || m.is_present("exclude"),
+            exclude: m.values_of("exclude")
+                .map(|v| v.map(String::from).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,97 @@
+//! Types describing a single dependency's update status.
+
+use std::fmt;
+
+/// A single dependency's version status, as reported by `Lockfile::get_updates`.
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    /// The crate name.
+    pub name: String,
+    /// The version currently locked in `Cargo.lock`.
+    pub project_ver: String,
+    /// The newest version that still satisfies the manifest's requirement, if any.
+    pub semver_ver: Option<String>,
+    /// The newest version published on the registry, regardless of compatibility.
+    pub latest_ver: Option<String>,
+    /// How many levels deep in the dependency graph this crate sits (root deps are depth 1).
+    pub depth: usize,
+    /// Set by `--audit` when the locked version matches a known RustSec advisory.
+    pub advisory: Option<Advisory>,
+    /// Whether the manifest has a direct `[dependencies]` requirement for this
+    /// crate. Only direct dependencies can be classified compatible/incompatible;
+    /// transitive dependencies have no requirement of their own to check against.
+    pub has_requirement: bool,
+}
+
+impl Dependency {
+    /// Creates a new `Dependency` with no update information yet.
+    pub fn new(name: &str, project_ver: &str, depth: usize) -> Self {
+        Dependency {
+            name: name.to_owned(),
+            project_ver: project_ver.to_owned(),
+            semver_ver: None,
+            latest_ver: None,
+            depth,
+            advisory: None,
+            has_requirement: false,
+        }
+    }
+
+    /// Classifies this update as semver-compatible or -incompatible with the
+    /// manifest's existing requirement, or `Unknown` when there's no direct
+    /// requirement to compare against (e.g. a transitive dependency).
+    pub fn kind(&self) -> UpdateKind {
+        if !self.has_requirement {
+            UpdateKind::Unknown
+        } else if self.semver_ver.is_some() && self.semver_ver == self.latest_ver {
+            // `semver_ver` is the newest version satisfying the requirement, so
+            // it equals `latest_ver` exactly when the latest version satisfies it.
+            UpdateKind::Compatible
+        } else {
+            UpdateKind::Incompatible
+        }
+    }
+}
+
+/// Whether an available update stays within the manifest's existing semver
+/// requirement, or would require bumping it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpdateKind {
+    /// The latest version still satisfies the existing requirement.
+    Compatible,
+    /// The latest version is a breaking change; the requirement would need to move.
+    Incompatible,
+    /// There's no direct requirement to classify against (a transitive dependency).
+    Unknown,
+}
+
+impl fmt::Display for UpdateKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UpdateKind::Compatible => write!(f, "Compatible"),
+            UpdateKind::Incompatible => write!(f, "Incompatible"),
+            UpdateKind::Unknown => write!(f, "  --  "),
+        }
+    }
+}
+
+/// A RustSec advisory matched against a dependency's locked version.
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    /// The advisory's RustSec identifier, e.g. `RUSTSEC-2020-0001`.
+    pub id: String,
+    /// The version requirements a patched release must satisfy.
+    pub patched: Vec<String>,
+}
+
+impl Advisory {
+    /// Formats the advisory for display: its id, plus the patched-version
+    /// range a fixed release must satisfy (e.g. `RUSTSEC-2020-0001 (patched: >=1.2.3)`).
+    pub fn display(&self) -> String {
+        if self.patched.is_empty() {
+            self.id.clone()
+        } else {
+            format!("{} (patched: {})", self.id, self.patched.join(", "))
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Error handling for the `cargo outdated` CLI.
+
+use std::fmt;
+use std::io;
+use std::process;
+
+use clap;
+
+use fmt::Format;
+
+/// The result type used throughout `cargo-outdated`.
+pub type CliResult<T> = Result<T, CliError>;
+
+/// Any error that can surface while running `cargo outdated`.
+#[derive(Debug)]
+pub enum CliError {
+    /// Argument parsing/validation failed; clap already knows how to print and exit for this.
+    Clap(clap::Error),
+    /// Parsing a `Cargo.toml`/`Cargo.lock` failed.
+    Toml(String),
+    /// An I/O operation failed.
+    Io(io::Error),
+    /// A catch-all for errors that don't fit the other variants.
+    Generic(String),
+}
+
+impl CliError {
+    /// Prints the error (in red, if colors are enabled) and exits the process.
+    ///
+    /// Clap errors defer to clap's own exit handling so `-h`/`--help` and usage
+    /// errors keep their normal formatting and exit codes.
+    pub fn exit(&self) -> ! {
+        if let CliError::Clap(ref e) = *self {
+            e.exit();
+        }
+        eprintln!("{}", Format::Error(self.to_string()));
+        process::exit(1)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CliError::Clap(ref e) => write!(f, "{}", e),
+            CliError::Toml(ref s) => write!(f, "{}", s),
+            CliError::Io(ref e) => write!(f, "{}", e),
+            CliError::Generic(ref s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<clap::Error> for CliError {
+    fn from(e: clap::Error) -> Self {
+        CliError::Clap(e)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl From<String> for CliError {
+    fn from(s: String) -> Self {
+        CliError::Generic(s)
+    }
+}
@@ -0,0 +1,39 @@
+//! Colorized terminal output helpers.
+
+use std::fmt;
+
+#[cfg(feature = "color")]
+use ansi_term::Colour::{Green, Red, Yellow};
+
+/// Wraps a displayable value with a semantic color (error, warning, or good news).
+///
+/// When the `color` feature is disabled this is purely a passthrough.
+#[derive(Debug, Copy, Clone)]
+pub enum Format<T> {
+    /// Something went wrong; rendered in red.
+    Error(T),
+    /// Worth the user's attention, but not fatal; rendered in yellow.
+    Warning(T),
+    /// Successful/expected outcome; rendered in green.
+    Good(T),
+}
+
+impl<T: fmt::Display> fmt::Display for Format<T> {
+    #[cfg(feature = "color")]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Format::Error(ref e) => write!(f, "{}", Red.bold().paint(&*format!("{}", e))),
+            Format::Warning(ref e) => write!(f, "{}", Yellow.paint(&*format!("{}", e))),
+            Format::Good(ref e) => write!(f, "{}", Green.paint(&*format!("{}", e))),
+        }
+    }
+
+    #[cfg(not(feature = "color"))]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Format::Error(ref e) | Format::Warning(ref e) | Format::Good(ref e) => {
+                write!(f, "{}", e)
+            }
+        }
+    }
+}
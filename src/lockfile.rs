@@ -0,0 +1,283 @@
+//! Reads `Cargo.lock`/`Cargo.toml` and figures out which locked dependencies
+//! have newer versions available on the registry.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use tempdir::TempDir;
+use toml;
+
+use config::Config;
+use deps::Dependency;
+use error::{CliError, CliResult};
+use util;
+
+/// The crates.io-index git repository, cloned locally so each package's
+/// published versions can be read without round-tripping to crates.io per
+/// dependency.
+const INDEX_URL: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// A parsed `Cargo.lock` plus the manifest it was generated from.
+#[derive(Debug)]
+pub struct Lockfile {
+    /// `name -> locked version` for every package in `Cargo.lock`.
+    locked: BTreeMap<String, String>,
+    /// `name -> version requirement string` for every `[dependencies]` entry
+    /// in the manifest (root package only).
+    requirements: BTreeMap<String, String>,
+    /// `name -> shortest distance from the root package` (root's direct
+    /// dependencies are depth 1), derived from `Cargo.lock`'s dependency edges.
+    depths: BTreeMap<String, usize>,
+}
+
+impl Lockfile {
+    /// Parses the lockfile and manifest referenced by `cfg`.
+    pub fn from_config(cfg: &Config) -> CliResult<Lockfile> {
+        let lock_toml = read_toml(&cfg.lockfile)?;
+        let manifest_toml = read_toml(&cfg.manifest_path)?;
+
+        let mut locked = BTreeMap::new();
+        let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        if let Some(toml::Value::Array(packages)) = lock_toml.get("package") {
+            for pkg in packages {
+                let name = pkg.get("name").and_then(|v| v.as_str());
+                let version = pkg.get("version").and_then(|v| v.as_str());
+                if let (Some(name), Some(version)) = (name, version) {
+                    locked.insert(name.to_owned(), version.to_owned());
+                }
+
+                if let Some(name) = name {
+                    let deps = pkg.get("dependencies")
+                        .and_then(|v| v.as_array())
+                        .map(|a| {
+                            a.iter()
+                                .filter_map(|v| v.as_str())
+                                .map(|s| s.split(' ').next().unwrap_or(s).to_owned())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    edges.insert(name.to_owned(), deps);
+                }
+            }
+        }
+
+        let root = cfg.root.clone().or_else(|| {
+            manifest_toml.get("package")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .map(String::from)
+        });
+        let depths = root.map(|r| shortest_depths(&r, &edges)).unwrap_or_default();
+
+        let mut requirements = BTreeMap::new();
+        if let Some(toml::Value::Table(deps)) = manifest_toml.get("dependencies") {
+            for (name, v) in deps {
+                let req = match *v {
+                    toml::Value::String(ref s) => s.clone(),
+                    toml::Value::Table(ref t) => {
+                        t.get("version")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("*")
+                            .to_owned()
+                    }
+                    _ => "*".to_owned(),
+                };
+                requirements.insert(name.clone(), req);
+            }
+        }
+
+        Ok(Lockfile {
+            locked,
+            requirements,
+            depths,
+        })
+    }
+
+    /// Computes the update status of every locked dependency (optionally
+    /// restricted by `cfg.packages`, and by `cfg.depth` against each
+    /// dependency's real distance from the root package in `Cargo.lock`'s
+    /// dependency graph).
+    ///
+    /// Returns `Ok(None)` when nothing in the lockfile has a newer version
+    /// available.
+    pub fn get_updates(&mut self, cfg: &Config) -> CliResult<Option<BTreeMap<String, Dependency>>> {
+        let index = clone_index()?;
+
+        let mut res = BTreeMap::new();
+        for (name, locked_ver) in &self.locked {
+            if !cfg.packages.is_empty() && !cfg.packages.contains(name) {
+                continue;
+            }
+
+            // If the root package's name couldn't be determined (or isn't in
+            // the lockfile's dependency graph at all), fall back to treating
+            // every package as depth 1 rather than silently reporting nothing.
+            let depth = if self.depths.is_empty() {
+                1
+            } else {
+                match self.depths.get(name) {
+                    Some(&d) => d,
+                    None => continue,
+                }
+            };
+            if let Some(max_depth) = cfg.depth {
+                if depth > max_depth {
+                    continue;
+                }
+            }
+
+            let versions = read_index_versions(&index, name);
+            let latest = versions.iter().max_by(util::compare_versions).cloned();
+
+            let mut dep = Dependency::new(name, locked_ver, depth);
+            if let Some(ref latest_ver) = latest {
+                if latest_ver != locked_ver {
+                    dep.latest_ver = Some(latest_ver.clone());
+                    if let Some(req) = self.requirements.get(name) {
+                        dep.has_requirement = true;
+                        // `latest` itself may be a breaking bump; scan every published
+                        // version for the newest one that still satisfies `req`, rather
+                        // than only testing `latest_ver`.
+                        dep.semver_ver = versions.iter()
+                            .filter(|v| util::is_compatible(req, v.as_str()))
+                            .max_by(util::compare_versions)
+                            .cloned();
+                    }
+                    res.insert(name.clone(), dep);
+                }
+            }
+        }
+
+        if res.is_empty() { Ok(None) } else { Ok(Some(res)) }
+    }
+
+    /// The manifest's version requirement string for `name`, if it's a
+    /// direct `[dependencies]` entry of the root package.
+    pub fn requirement(&self, name: &str) -> Option<&str> {
+        self.requirements.get(name).map(|s| s.as_str())
+    }
+
+    /// Every package locked in `Cargo.lock`, as `name -> version`.
+    pub fn locked(&self) -> &BTreeMap<String, String> {
+        &self.locked
+    }
+}
+
+/// Computes each package's shortest distance from `root` over the
+/// dependency-edges declared in `Cargo.lock` (`root`'s direct dependencies
+/// are depth 1). Packages unreachable from `root` are left out of the map.
+fn shortest_depths(root: &str, edges: &BTreeMap<String, Vec<String>>) -> BTreeMap<String, usize> {
+    let mut depths = BTreeMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((root.to_owned(), 0));
+
+    while let Some((name, depth)) = queue.pop_front() {
+        if depths.contains_key(&name) {
+            continue;
+        }
+        depths.insert(name.clone(), depth);
+
+        if let Some(deps) = edges.get(&name) {
+            for dep in deps {
+                if !depths.contains_key(dep) {
+                    queue.push_back((dep.clone(), depth + 1));
+                }
+            }
+        }
+    }
+
+    depths.remove(root);
+    depths
+}
+
+/// Reads a file to a `String` and parses it as TOML.
+fn read_toml(path: &Path) -> CliResult<toml::Value> {
+    let mut s = String::new();
+    File::open(path)?.read_to_string(&mut s)?;
+    s.parse::<toml::Value>()
+        .map_err(|e| CliError::Toml(format!("failed to parse {}: {}", path.display(), e)))
+}
+
+/// Clones the crates.io-index into a scratch directory so it can be scanned
+/// for published versions.
+fn clone_index() -> CliResult<TempDir> {
+    let dir = TempDir::new("cargo-outdated")?;
+    let status = ::std::process::Command::new("git")
+        .args(["clone", "--depth", "1", INDEX_URL])
+        .arg(dir.path())
+        .status();
+    match status {
+        Ok(ref s) if s.success() => Ok(dir),
+        _ => Err(CliError::Generic("failed to clone the crates.io index".to_owned())),
+    }
+}
+
+/// Looks up every published version of `name` recorded in a local clone of
+/// the crates.io-index.
+fn read_index_versions(index: &TempDir, name: &str) -> Vec<String> {
+    let path = index_path(index.path(), name);
+    let mut s = String::new();
+    if File::open(&path).and_then(|mut f| f.read_to_string(&mut s)).is_err() {
+        return Vec::new();
+    }
+
+    s.lines()
+        .filter_map(|line| {
+            // Each line is a small hand-rolled JSON object; we only need "vers".
+            line.split("\"vers\":\"")
+                .nth(1)
+                .and_then(|rest| rest.split('"').next())
+                .map(String::from)
+        })
+        .collect()
+}
+
+/// crates.io-index shards packages by name length, mirroring cargo's own layout.
+fn index_path(root: &Path, name: &str) -> PathBuf {
+    match name.len() {
+        1 => root.join("1").join(name),
+        2 => root.join("2").join(name),
+        3 => root.join("3").join(&name[..1]).join(name),
+        _ => root.join(&name[0..2]).join(&name[2..4]).join(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortest_depths_direct_and_transitive() {
+        let mut edges = BTreeMap::new();
+        edges.insert("root".to_owned(), vec!["a".to_owned(), "b".to_owned()]);
+        edges.insert("a".to_owned(), vec!["c".to_owned()]);
+
+        let depths = shortest_depths("root", &edges);
+        assert_eq!(depths.get("a"), Some(&1));
+        assert_eq!(depths.get("b"), Some(&1));
+        assert_eq!(depths.get("c"), Some(&2));
+        assert_eq!(depths.get("root"), None);
+    }
+
+    #[test]
+    fn shortest_depths_picks_the_shorter_path() {
+        let mut edges = BTreeMap::new();
+        edges.insert("root".to_owned(), vec!["a".to_owned(), "b".to_owned()]);
+        edges.insert("a".to_owned(), vec!["b".to_owned()]);
+
+        let depths = shortest_depths("root", &edges);
+        assert_eq!(depths.get("b"), Some(&1));
+    }
+
+    #[test]
+    fn shortest_depths_unreachable_packages_are_excluded() {
+        let mut edges = BTreeMap::new();
+        edges.insert("root".to_owned(), vec!["a".to_owned()]);
+        edges.insert("stray".to_owned(), vec![]);
+
+        let depths = shortest_depths("root", &edges);
+        assert_eq!(depths.get("stray"), None);
+    }
+}
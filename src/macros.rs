@@ -0,0 +1,39 @@
+// Debug and verbosity helper macros, modeled after clap's own internal macros.
+
+#[cfg(feature = "debug")]
+macro_rules! debugln {
+    ($fmt:expr) => (println!(concat!("[DEBUG] ", $fmt)));
+    ($fmt:expr, $($arg:tt)*) => (println!(concat!("[DEBUG] ", $fmt), $($arg)*));
+}
+
+#[cfg(not(feature = "debug"))]
+macro_rules! debugln {
+    ($fmt:expr) => ();
+    ($fmt:expr, $($arg:tt)*) => ();
+}
+
+macro_rules! verbose {
+    ($cfg:expr, $fmt:expr) => {
+        if $cfg.verbose {
+            print!($fmt);
+        }
+    };
+    ($cfg:expr, $fmt:expr, $($arg:tt)*) => {
+        if $cfg.verbose {
+            print!($fmt, $($arg)*);
+        }
+    };
+}
+
+macro_rules! verboseln {
+    ($cfg:expr, $fmt:expr) => {
+        if $cfg.verbose {
+            println!($fmt);
+        }
+    };
+    ($cfg:expr, $fmt:expr, $($arg:tt)*) => {
+        if $cfg.verbose {
+            println!($fmt, $($arg)*);
+        }
+    };
+}
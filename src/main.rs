@@ -101,8 +101,11 @@ mod config;
 mod lockfile;
 mod deps;
 mod error;
+mod advisory;
 mod fmt;
+mod upgrade;
 mod util;
+mod workspace;
 
 use std::io::{Write, stdout};
 use std::path::Path;
@@ -113,10 +116,12 @@ use std::process;
 use clap::{App, AppSettings, Arg, SubCommand, ArgMatches};
 use tabwriter::TabWriter;
 
-use config::Config;
+use config::{Config, OutputFormat};
+use deps::Dependency;
 use lockfile::Lockfile;
 use error::CliResult;
 use fmt::Format;
+use util::json_escape;
 
 fn main() {
     debugln!("main:args={:?}", env::args().collect::<Vec<_>>());
@@ -153,7 +158,39 @@ fn main() {
                     .validator(is_file),
                 Arg::from_usage("-l, --lockfile-path [PATH] 'An absolute path to the Cargo.lock to use \
                                                              (Defaults to Cargo.lock in project root)'")
-                    .validator(is_file)]))
+                    .validator(is_file),
+                Arg::from_usage("--format [FORMAT] 'Output format of the report \
+                                                    (Defaults to human-readable table) [values: human, json]'")
+                    .possible_values(&["human", "json"])
+                    .default_value("human"),
+                Arg::from_usage("--upgrade 'Rewrite Cargo.toml requirements for outdated dependencies'"),
+                Arg::from_usage("--dry-run 'With --upgrade, show what would change without writing it'")
+                    .requires("upgrade"),
+                Arg::from_usage("--compatible 'With --upgrade, also rewrite requirements that are \
+                                               already semver-compatible with the latest version'")
+                    .requires("upgrade"),
+                Arg::from_usage("--force 'With --upgrade, also rewrite pinned (=) requirements'")
+                    .requires("upgrade"),
+                Arg::from_usage("--audit 'Check locked dependencies against the RustSec advisory \
+                                          database'"),
+                Arg::from_usage("--audit-exit-code [NUM] 'The exit code to return when --audit finds \
+                                                          a vulnerable dependency'")
+                    .default_value("1"),
+                Arg::from_usage("--compatible-only 'Only report updates that stay within the \
+                                                     manifest's existing requirement'")
+                    .conflicts_with("incompatible-only"),
+                Arg::from_usage("--incompatible-only 'Only report updates that would require a \
+                                                       breaking requirement bump'"),
+                Arg::from_usage("--compatible-exit-code [NUM] 'The exit code to return when only \
+                                                                compatible updates are found \
+                                                                (Defaults to --exit-code)'"),
+                Arg::from_usage("--incompatible-exit-code [NUM] 'The exit code to return when an \
+                                                                  incompatible update is found \
+                                                                  (Defaults to --exit-code)'"),
+                Arg::from_usage("--workspace 'Report on every workspace member, not just the root \
+                                              package'"),
+                Arg::from_usage("--exclude [MEMBER]...  'Workspace member to exclude from the report \
+                                                         (implies --workspace)'")]))
         .get_matches();
 
     if let Some(m) = m.subcommand_matches("outdated") {
@@ -169,46 +206,175 @@ fn main() {
 
 fn execute(m: &ArgMatches) -> CliResult<i32> {
     debugln!("execute:m={:#?}", m);
-    let cfg = try!(Config::from_matches(m));
+    let cfg = Config::from_matches(m)?;
+
+    let members = workspace::members(&cfg.manifest_path)?;
+    // A virtual manifest (`[workspace]` with no `[package]` of its own) has no
+    // root crate to fall back to, so it's reported across every member even
+    // without an explicit `--workspace`/`--exclude` flag.
+    let aggregate = cfg.workspace || workspace::is_virtual(&cfg.manifest_path);
+    match members {
+        Some(ref paths) if aggregate => {
+            let mut worst = 0;
+            for path in paths {
+                let name = workspace::member_name(path);
+                if cfg.exclude.contains(&name) {
+                    continue;
+                }
+
+                println!("{}\n", Format::Good(format!("== {} ==", name)));
+                let mut member_cfg = cfg.clone();
+                member_cfg.manifest_path = path.clone();
+                let code = report(&member_cfg)?;
+                worst = worst.max(code);
+                println!();
+            }
+            Ok(worst)
+        }
+        _ => report(&cfg),
+    }
+}
 
+/// Reports on a single manifest/lockfile pair (one workspace member, or the
+/// whole project when it isn't a workspace).
+fn report(cfg: &Config) -> CliResult<i32> {
     verbose!(cfg, "Parsing {}...", Format::Warning(cfg.lockfile.to_string_lossy()));
 
-    let mut lf = try!(Lockfile::from_config(&cfg));
+    let mut lf = Lockfile::from_config(cfg)?;
     verboseln!(cfg, "{}", Format::Good("Done"));
 
-    match lf.get_updates(&cfg) {
-        Ok(Some(res)) => {
-            println!("The following dependencies have newer versions available:\n");
-            let mut tw = TabWriter::new(vec![]);
-            write!(&mut tw, "\tName\tProject Ver\tSemVer Compat\tLatest Ver\n")
-                .unwrap_or_else(|e| panic!("write! error: {}", e));
-            for d in res.values() {
-                write!(&mut tw,
-                       "\t{}\t   {}\t   {}\t  {}\n",
-                       d.name,
-                       d.project_ver,
-                       d.semver_ver
-                        .as_ref()
-                        .unwrap_or(&String::from("  --  ")),
-                       d.latest_ver
-                        .as_ref()
-                        .unwrap_or(&String::from("  --  ")))
-                    .unwrap();
-            }
-            tw.flush().unwrap_or_else(|e| panic!("failed to flush TabWriter: {}", e));
-            write!(stdout(),
-                   "{}",
-                   String::from_utf8(tw.into_inner().unwrap())
-                       .unwrap_or_else(|e| panic!("from_utf8 error: {}", e)))
-                .unwrap_or_else(|e| panic!("write! error: {}", e));
-            Ok(cfg.exit_code)
+    let mut res = lf.get_updates(cfg)?.unwrap_or_default();
+
+    let mut any_vulnerable = false;
+    if cfg.audit {
+        let vulnerable = advisory::check(lf.locked())?;
+        any_vulnerable = !vulnerable.is_empty();
+        for (name, advisory) in vulnerable {
+            res.entry(name.clone())
+                .or_insert_with(|| {
+                    let ver = lf.locked().get(&name).cloned().unwrap_or_default();
+                    Dependency::new(&name, &ver, 1)
+                })
+                .advisory = Some(advisory);
         }
-        Ok(None) => {
+    }
+
+    if cfg.compatible_only {
+        res.retain(|_, d| d.kind() == deps::UpdateKind::Compatible);
+    } else if cfg.incompatible_only {
+        res.retain(|_, d| d.kind() == deps::UpdateKind::Incompatible);
+    }
+
+    if res.is_empty() {
+        if cfg.format == OutputFormat::Json {
+            println!("[]");
+        } else {
             println!("All dependencies are up to date, yay!");
-            Ok(0)
         }
-        Err(e) => Err(e),
+        return Ok(0);
+    }
+
+    if cfg.upgrade {
+        return print_upgrade(cfg, &lf, &res);
+    }
+
+    let code = exit_code(cfg, any_vulnerable, &res);
+
+    if cfg.format == OutputFormat::Json {
+        return Ok(print_json(&res, code));
+    }
+
+    println!("The following dependencies have newer versions available:\n");
+    let mut tw = TabWriter::new(vec![]);
+    writeln!(&mut tw, "\tName\tProject Ver\tSemVer Compat\tLatest Ver\tKind\tAdvisory")
+        .unwrap_or_else(|e| panic!("write! error: {}", e));
+    for d in res.values() {
+        writeln!(&mut tw,
+               "\t{}\t   {}\t   {}\t  {}\t  {}\t  {}",
+               d.name,
+               d.project_ver,
+               d.semver_ver
+                .as_ref()
+                .unwrap_or(&String::from("  --  ")),
+               d.latest_ver
+                .as_ref()
+                .unwrap_or(&String::from("  --  ")),
+               d.kind(),
+               d.advisory
+                .as_ref()
+                .map(|a| a.display())
+                .unwrap_or_else(|| "  --  ".to_owned()))
+            .unwrap();
+    }
+    tw.flush().unwrap_or_else(|e| panic!("failed to flush TabWriter: {}", e));
+    write!(stdout(),
+           "{}",
+           String::from_utf8(tw.into_inner().unwrap())
+               .unwrap_or_else(|e| panic!("from_utf8 error: {}", e)))
+        .unwrap_or_else(|e| panic!("write! error: {}", e));
+    Ok(code)
+}
+
+/// Picks the process exit code: `--audit-exit-code` takes priority when a
+/// vulnerable dependency was found, otherwise the result is classified as
+/// compatible or incompatible and the matching threshold is used; updates
+/// that can't be classified (transitive dependencies) fall back to `--exit-code`.
+fn exit_code(cfg: &Config, any_vulnerable: bool, res: &::std::collections::BTreeMap<String, Dependency>) -> i32 {
+    if any_vulnerable {
+        return cfg.audit_exit_code;
+    }
+    if res.values().any(|d| d.kind() == deps::UpdateKind::Incompatible) {
+        cfg.incompatible_exit_code
+    } else if res.values().any(|d| d.kind() == deps::UpdateKind::Compatible) {
+        cfg.compatible_exit_code
+    } else {
+        cfg.exit_code
+    }
+}
+
+/// Rewrites `Cargo.toml` requirements for `res` (or reports what would
+/// change, under `--dry-run`) and prints a summary of the result.
+fn print_upgrade(cfg: &Config,
+                  lf: &Lockfile,
+                  res: &::std::collections::BTreeMap<String, ::deps::Dependency>)
+                  -> CliResult<i32> {
+    let rewrites = upgrade::run(cfg, lf, res)?;
+    if rewrites.is_empty() {
+        println!("No requirements needed rewriting.");
+        return Ok(0);
+    }
+
+    let verb = if cfg.dry_run { "Would rewrite" } else { "Rewrote" };
+    for r in &rewrites {
+        println!("{} {} from \"{}\" to \"{}\"", verb, r.name, r.from, r.to);
+    }
+    Ok(cfg.exit_code)
+}
+
+/// Prints `res` as a JSON array (one object per outdated dependency) and
+/// returns the exit code `execute` should return for it.
+fn print_json(res: &::std::collections::BTreeMap<String, ::deps::Dependency>, exit_code: i32) -> i32 {
+    let mut objects = Vec::with_capacity(res.len());
+    for d in res.values() {
+        objects.push(format!(
+            "{{\"name\":\"{}\",\"project_ver\":\"{}\",\"semver_ver\":{},\"latest_ver\":{},\"depth\":{},\
+             \"advisory\":{}}}",
+            json_escape(&d.name),
+            json_escape(&d.project_ver),
+            d.semver_ver.as_ref().map_or("null".to_owned(), |v| format!("\"{}\"", json_escape(v))),
+            d.latest_ver.as_ref().map_or("null".to_owned(), |v| format!("\"{}\"", json_escape(v))),
+            d.depth,
+            d.advisory.as_ref().map_or("null".to_owned(), |a| {
+                let patched = a.patched
+                    .iter()
+                    .map(|p| format!("\"{}\"", json_escape(p)))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{{\"id\":\"{}\",\"patched\":[{}]}}", json_escape(&a.id), patched)
+            })));
     }
+    println!("[{}]", objects.join(","));
+    exit_code
 }
 
 fn is_file(s: String) -> Result<(), String> {
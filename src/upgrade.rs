@@ -0,0 +1,190 @@
+//! Rewrites `Cargo.toml` dependency requirements in place for `--upgrade`.
+//!
+//! Edits are surgical: only the quoted requirement string for a touched
+//! dependency is replaced, so comments, key ordering, and surrounding
+//! whitespace in the manifest are left exactly as the user wrote them.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+
+use config::Config;
+use deps::Dependency;
+use error::CliResult;
+use lockfile::Lockfile;
+use util;
+
+/// One dependency requirement that was (or would be) rewritten.
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    /// The crate name.
+    pub name: String,
+    /// The requirement string as it appeared in the manifest before rewriting.
+    pub from: String,
+    /// The requirement string it was (or would be) rewritten to.
+    pub to: String,
+}
+
+/// Rewrites `cfg.manifest_path`'s `[dependencies]` requirements for every
+/// dependency in `updates`, honoring `--compatible`/`--dry-run`/`--force`.
+///
+/// Returns the rewrites that were made (or, under `--dry-run`, would have
+/// been made) without touching the manifest.
+pub fn run(cfg: &Config,
+           lf: &Lockfile,
+           updates: &BTreeMap<String, Dependency>)
+           -> CliResult<Vec<Rewrite>> {
+    let mut text = String::new();
+    File::open(&cfg.manifest_path)?.read_to_string(&mut text)?;
+
+    let mut rewrites = Vec::new();
+    for dep in updates.values() {
+        let req = match lf.requirement(&dep.name) {
+            Some(r) => r,
+            None => continue,
+        };
+        let latest = match dep.latest_ver {
+            Some(ref v) => v,
+            None => continue,
+        };
+
+        let pinned = req.trim_start().starts_with('=');
+        if pinned && !cfg.force {
+            continue;
+        }
+
+        let compatible = util::is_compatible(req, latest);
+        if compatible && !cfg.upgrade_compatible {
+            continue;
+        }
+
+        let new_req = util::derive_requirement(req, latest);
+        if new_req == req {
+            // E.g. a `*` requirement: already matches anything, nothing to rewrite.
+            continue;
+        }
+        if let Some(new_text) = replace_requirement(&text, &dep.name, req, &new_req) {
+            text = new_text;
+            rewrites.push(Rewrite {
+                name: dep.name.clone(),
+                from: req.to_owned(),
+                to: new_req,
+            });
+        }
+    }
+
+    if !rewrites.is_empty() && !cfg.dry_run {
+        File::create(&cfg.manifest_path)?.write_all(text.as_bytes())?;
+    }
+
+    Ok(rewrites)
+}
+
+/// Finds the line declaring `name = "req"` (or `name = { version = "req", ... }`)
+/// under `[dependencies]` and replaces only the quoted requirement on that
+/// line, leaving every other byte of the manifest untouched.
+///
+/// The search is scoped to the root `[dependencies]` table, since that's the
+/// only table `Lockfile::from_config` reads requirements from: a same-named
+/// key under `[dev-dependencies]`, `[build-dependencies]`, or a
+/// `[target.'...'.dependencies]` table is never a match.
+fn replace_requirement(text: &str, name: &str, req: &str, new_req: &str) -> Option<String> {
+    let (start, end) = dependencies_table_range(text)?;
+
+    let rel_line_start = find_key_line(&text[start..end], name)?;
+    let line_start = start + rel_line_start;
+    let line_end = text[line_start..end].find('\n').map(|i| line_start + i).unwrap_or(end);
+
+    let needle = format!("\"{}\"", req);
+    let needle_pos = text[line_start..line_end].find(&needle)? + line_start;
+
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..needle_pos]);
+    out.push('"');
+    out.push_str(new_req);
+    out.push('"');
+    out.push_str(&text[needle_pos + needle.len()..]);
+    Some(out)
+}
+
+/// Returns the byte range of the root `[dependencies]` table's body: from
+/// just after the `[dependencies]` header line through the line before the
+/// next top-level `[...]` header, or the end of the file. Returns `None` if
+/// the manifest has no such table.
+fn dependencies_table_range(text: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut start = None;
+    for line in text.split('\n') {
+        let line_len = line.len() + 1;
+        if let Some(table_start) = start {
+            if line.trim_start().starts_with('[') {
+                return Some((table_start, offset));
+            }
+        } else if line.trim() == "[dependencies]" {
+            start = Some(offset + line_len);
+        }
+        offset += line_len;
+    }
+    start.map(|s| (s, text.len()))
+}
+
+/// Returns the byte offset of the start of the line whose first token is
+/// `name` immediately followed (ignoring whitespace) by `=`, i.e. a
+/// top-level `name = ...` key, not merely a substring match inside some
+/// other key (e.g. `runtime = ` when `name` is `time`).
+fn find_key_line(text: &str, name: &str) -> Option<usize> {
+    let mut search_from = 0;
+    while let Some(rel) = text[search_from..].find(name) {
+        let abs = search_from + rel;
+        let line_start = text[..abs].rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+        let is_first_token = text[line_start..abs].chars().all(char::is_whitespace);
+        let followed_by_eq = text[abs + name.len()..].trim_start().starts_with('=');
+        if is_first_token && followed_by_eq {
+            return Some(line_start);
+        }
+
+        search_from = abs + name.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_requirement_rewrites_only_the_matching_dependency() {
+        let text = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\ntime = \"0.1\"\nruntime = \"0.1\"\n";
+        let out = replace_requirement(text, "time", "0.1", "0.2").unwrap();
+        assert_eq!(out,
+                   "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[dependencies]\ntime = \"0.2\"\nruntime = \"0.1\"\n");
+    }
+
+    #[test]
+    fn replace_requirement_ignores_same_named_key_outside_dependencies() {
+        let text = "[dev-dependencies]\ntime = \"0.1\"\n\n[dependencies]\ntime = \"0.2\"\n";
+        let out = replace_requirement(text, "time", "0.2", "0.3").unwrap();
+        assert_eq!(out, "[dev-dependencies]\ntime = \"0.1\"\n\n[dependencies]\ntime = \"0.3\"\n");
+    }
+
+    #[test]
+    fn replace_requirement_none_without_a_dependencies_table() {
+        let text = "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n";
+        assert!(replace_requirement(text, "time", "0.1", "0.2").is_none());
+    }
+
+    #[test]
+    fn find_key_line_skips_substring_matches() {
+        let table = "runtime = \"0.1\"\ntime = \"0.2\"\n";
+        let line_start = find_key_line(table, "time").unwrap();
+        assert_eq!(&table[line_start..], "time = \"0.2\"\n");
+    }
+
+    #[test]
+    fn dependencies_table_range_stops_at_next_header() {
+        let text = "[dependencies]\ntime = \"0.1\"\n\n[dev-dependencies]\nfoo = \"0.1\"\n";
+        let (start, end) = dependencies_table_range(text).unwrap();
+        assert_eq!(&text[start..end], "time = \"0.1\"\n\n");
+    }
+}
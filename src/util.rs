@@ -0,0 +1,154 @@
+//! Small helpers shared by the `lockfile`, `advisory`, `upgrade`, and `main` modules.
+
+use std::cmp::Ordering;
+
+/// Returns true when `latest` satisfies the semver compatibility rules cargo
+/// itself uses for a `^`/bare requirement derived from `current` (i.e. they
+/// share the same left-most non-zero component).
+pub fn is_compatible(current: &str, latest: &str) -> bool {
+    let cur: Vec<&str> = current.trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split('.')
+        .collect();
+    if cur == [""] {
+        // `*` (or any requirement with no numeric component at all) matches
+        // every published version.
+        return true;
+    }
+    let lat: Vec<&str> = latest.trim_start_matches(|c: char| !c.is_ascii_digit())
+        .split('.')
+        .collect();
+
+    let first_nonzero = cur.iter().position(|p| p.parse::<u64>().map(|n| n != 0).unwrap_or(false));
+    match first_nonzero {
+        Some(idx) => cur.get(0..=idx) == lat.get(0..=idx),
+        None => cur == lat,
+    }
+}
+
+/// Breaks a semver-ish version string into its numeric components, newest last.
+fn numeric_parts(v: &str) -> Vec<u64> {
+    v.split(['.', '-', '+'])
+        .map(|p| p.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Orders two semver-ish version strings, newest last.
+pub fn compare_versions(a: &&String, b: &&String) -> Ordering {
+    numeric_parts(a).cmp(&numeric_parts(b))
+}
+
+/// Returns true when `version` satisfies a comparator requirement like
+/// `>= 1.2.3`, `< 2.0.0`, or `= 1.0.0`, as used by RustSec advisories.
+///
+/// Requirements may be a comma-separated list of comparators (e.g.
+/// `">= 2.0.0, < 3.0.0"`), in which case every comparator must hold.
+pub fn satisfies(version: &str, req: &str) -> bool {
+    req.split(',').all(|part| satisfies_one(version, part))
+}
+
+/// Returns true when `version` satisfies a single comparator, e.g. `>= 1.2.3`.
+fn satisfies_one(version: &str, req: &str) -> bool {
+    let req = req.trim();
+    let (rest, cmp): (&str, fn(Ordering) -> bool) = if let Some(r) = req.strip_prefix(">=") {
+        (r, |o| o != Ordering::Less)
+    } else if let Some(r) = req.strip_prefix('>') {
+        (r, |o| o == Ordering::Greater)
+    } else if let Some(r) = req.strip_prefix("<=") {
+        (r, |o| o != Ordering::Greater)
+    } else if let Some(r) = req.strip_prefix('<') {
+        (r, |o| o == Ordering::Less)
+    } else {
+        (req.trim_start_matches('='), |o| o == Ordering::Equal)
+    };
+
+    let have = numeric_parts(version);
+    let want = numeric_parts(rest.trim());
+    cmp(have.cmp(&want))
+}
+
+/// Derives a new requirement string for `latest`, preserving `req`'s operator
+/// prefix (e.g. `^`, `~`) and precision (number of dot-separated components),
+/// so bumping `"0.12"` against `0.13.4` yields `"0.13"`, not `"0.13.4"`.
+pub fn derive_requirement(req: &str, latest: &str) -> String {
+    let req = req.trim();
+    let prefix_len = req.len() - req.trim_start_matches(|c: char| !c.is_ascii_digit()).len();
+    let (prefix, numeric) = req.split_at(prefix_len);
+    if numeric.is_empty() {
+        // `*` (or any requirement with no numeric component) already matches
+        // every version; there's nothing to derive a new requirement from.
+        return req.to_owned();
+    }
+
+    let precision = numeric.split('.').count().max(1);
+    let new_numeric: Vec<&str> = latest.split('.').take(precision).collect();
+
+    format!("{}{}", prefix, new_numeric.join("."))
+}
+
+/// Escapes a string for embedding in a hand-written JSON document.
+pub fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_compatible_same_leading_nonzero() {
+        assert!(is_compatible("1.2.3", "1.9.0"));
+        assert!(!is_compatible("1.2.3", "2.0.0"));
+    }
+
+    #[test]
+    fn is_compatible_leading_zero_component() {
+        assert!(is_compatible("0.2.3", "0.2.9"));
+        assert!(!is_compatible("0.2.3", "0.3.0"));
+    }
+
+    #[test]
+    fn is_compatible_wildcard_matches_anything() {
+        assert!(is_compatible("*", "0.1.0"));
+        assert!(is_compatible("*", "9.9.9"));
+    }
+
+    #[test]
+    fn satisfies_single_comparator() {
+        assert!(satisfies("1.2.3", ">= 1.0.0"));
+        assert!(!satisfies("1.2.3", "< 1.0.0"));
+        assert!(satisfies("1.2.3", "= 1.2.3"));
+    }
+
+    #[test]
+    fn satisfies_compound_range_requires_all_comparators() {
+        assert!(satisfies("2.5.0", ">= 2.0.0, < 3.0.0"));
+        assert!(!satisfies("3.0.0", ">= 2.0.0, < 3.0.0"));
+    }
+
+    #[test]
+    fn derive_requirement_preserves_prefix_and_precision() {
+        assert_eq!(derive_requirement("^0.12", "0.13.4"), "^0.13");
+        assert_eq!(derive_requirement("~1.2.3", "1.2.9"), "~1.2.9");
+        assert_eq!(derive_requirement("1", "2.0.0"), "2");
+    }
+
+    #[test]
+    fn derive_requirement_wildcard_is_left_alone() {
+        assert_eq!(derive_requirement("*", "1.2.3"), "*");
+    }
+
+    #[test]
+    fn json_escape_handles_special_characters() {
+        assert_eq!(json_escape("plain"), "plain");
+        assert_eq!(json_escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
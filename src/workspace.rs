@@ -0,0 +1,86 @@
+//! Enumerates workspace member manifests for `--workspace` reporting.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use error::CliResult;
+
+/// Returns the member manifest paths declared in `manifest_path`'s
+/// `[workspace]` table, honoring literal member paths as well as simple
+/// `crates/*`-style globs. Returns `None` when `manifest_path` isn't a
+/// workspace root at all.
+pub fn members(manifest_path: &Path) -> CliResult<Option<Vec<PathBuf>>> {
+    let doc = read_toml(manifest_path)?;
+
+    let workspace = match doc.get("workspace") {
+        Some(w) => w,
+        None => return Ok(None),
+    };
+
+    let root = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut paths = Vec::new();
+    if let Some(members) = workspace.get("members").and_then(|m| m.as_array()) {
+        for m in members {
+            let pattern = match m.as_str() {
+                Some(s) => s,
+                None => continue,
+            };
+            if let Some(prefix) = pattern.strip_suffix("/*") {
+                let dir = root.join(prefix);
+                if let Ok(entries) = dir.read_dir() {
+                    for entry in entries.filter_map(|e| e.ok()) {
+                        let manifest = entry.path().join("Cargo.toml");
+                        if manifest.is_file() {
+                            paths.push(manifest);
+                        }
+                    }
+                }
+            } else {
+                paths.push(root.join(pattern).join("Cargo.toml"));
+            }
+        }
+    }
+
+    // A manifest can declare both `[package]` and `[workspace]` at once; that
+    // root package is itself a member.
+    if doc.get("package").is_some() {
+        paths.insert(0, manifest_path.to_path_buf());
+    }
+
+    Ok(Some(paths))
+}
+
+/// Returns `true` when `manifest_path` is a *virtual* workspace manifest,
+/// i.e. it declares `[workspace]` but has no `[package]` of its own. A
+/// virtual manifest has no root crate to report on, so callers must fall
+/// back to reporting every member instead.
+pub fn is_virtual(manifest_path: &Path) -> bool {
+    match read_toml(manifest_path) {
+        Ok(doc) => doc.get("workspace").is_some() && doc.get("package").is_none(),
+        Err(_) => false,
+    }
+}
+
+/// The package name declared in a member manifest's `[package]` table, or its
+/// directory name if that's missing.
+pub fn member_name(manifest_path: &Path) -> String {
+    if let Ok(doc) = read_toml(manifest_path) {
+        if let Some(name) = doc.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+            return name.to_owned();
+        }
+    }
+    manifest_path.parent()
+        .and_then(|p| p.file_name())
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| manifest_path.to_string_lossy().into_owned())
+}
+
+fn read_toml(path: &Path) -> CliResult<toml::Value> {
+    let mut s = String::new();
+    File::open(path)?.read_to_string(&mut s)?;
+    s.parse::<toml::Value>()
+        .map_err(|e| ::error::CliError::Toml(format!("failed to parse {}: {}", path.display(), e)))
+}